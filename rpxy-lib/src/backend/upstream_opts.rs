@@ -1,4 +1,8 @@
 use crate::error::*;
+use hyper::header::{HeaderMap, HeaderName, HeaderValue};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use x509_parser::prelude::*;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum UpstreamOption {
@@ -6,17 +10,140 @@ pub enum UpstreamOption {
   UpgradeInsecureRequests,
   ForceHttp11Upstream,
   ForceHttp2Upstream,
+  /// Forward the verified peer certificate metadata to the upstream as
+  /// `X-SSL-Client-*` request headers (subject DN, issuer DN, serial and the
+  /// SHA-256 fingerprint). Applied by [`apply_client_cert_headers`], which also
+  /// strips any client-supplied `X-SSL-Client-*` headers so they cannot be
+  /// spoofed.
+  ForwardClientCertHeaders,
   // TODO: Adds more options for heder override
 }
 impl TryFrom<&str> for UpstreamOption {
   type Error = RpxyError;
   fn try_from(val: &str) -> RpxyResult<Self> {
     match val {
-      "diaable_override_host" => Ok(Self::DisableOverrideHost),
+      "disable_override_host" => Ok(Self::DisableOverrideHost),
       "upgrade_insecure_requests" => Ok(Self::UpgradeInsecureRequests),
       "force_http11_upstream" => Ok(Self::ForceHttp11Upstream),
       "force_http2_upstream" => Ok(Self::ForceHttp2Upstream),
+      "forward_client_cert_headers" => Ok(Self::ForwardClientCertHeaders),
       _ => Err(RpxyError::UnsupportedUpstreamOption),
     }
   }
 }
+
+const X_SSL_CLIENT_PREFIX: &str = "x-ssl-client-";
+const X_SSL_CLIENT_SUBJECT: &str = "x-ssl-client-subject";
+const X_SSL_CLIENT_ISSUER: &str = "x-ssl-client-issuer";
+const X_SSL_CLIENT_SERIAL: &str = "x-ssl-client-serial";
+const X_SSL_CLIENT_SHA256: &str = "x-ssl-client-sha256";
+
+/// Apply the client-certificate header policy to an outgoing upstream request.
+///
+/// Any incoming `X-SSL-Client-*` headers are always removed so a client cannot
+/// forge its own identity. When [`UpstreamOption::ForwardClientCertHeaders`] is
+/// enabled and a verified peer certificate is present, its metadata is injected
+/// in their place.
+pub fn apply_client_cert_headers(headers: &mut HeaderMap, opts: &HashSet<UpstreamOption>, peer_cert_der: Option<&[u8]>) {
+  strip_client_cert_headers(headers);
+  if opts.contains(&UpstreamOption::ForwardClientCertHeaders) {
+    if let Some(der) = peer_cert_der {
+      inject_client_cert_headers(headers, der);
+    }
+  }
+}
+
+/// Remove every incoming `X-SSL-Client-*` header, preventing a client from
+/// spoofing the certificate metadata that the upstream trusts rpxy to set.
+fn strip_client_cert_headers(headers: &mut HeaderMap) {
+  let spoofed: Vec<HeaderName> = headers
+    .keys()
+    .filter(|name| name.as_str().starts_with(X_SSL_CLIENT_PREFIX))
+    .cloned()
+    .collect();
+  for name in spoofed {
+    headers.remove(&name);
+  }
+}
+
+/// Inject the verified peer certificate metadata as `X-SSL-Client-*` headers.
+/// The SHA-256 fingerprint is always added; the subject/issuer DN and serial are
+/// added on a best-effort basis and skipped if the certificate cannot be parsed.
+fn inject_client_cert_headers(headers: &mut HeaderMap, cert_der: &[u8]) {
+  if let Ok(value) = HeaderValue::from_str(&fingerprint_sha256(cert_der)) {
+    headers.insert(HeaderName::from_static(X_SSL_CLIENT_SHA256), value);
+  }
+
+  if let Ok((_, cert)) = X509Certificate::from_der(cert_der) {
+    if let Ok(value) = HeaderValue::from_str(&cert.subject().to_string()) {
+      headers.insert(HeaderName::from_static(X_SSL_CLIENT_SUBJECT), value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&cert.issuer().to_string()) {
+      headers.insert(HeaderName::from_static(X_SSL_CLIENT_ISSUER), value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&cert.raw_serial_as_string()) {
+      headers.insert(HeaderName::from_static(X_SSL_CLIENT_SERIAL), value);
+    }
+  }
+}
+
+/// Colon-separated uppercase hex of the certificate's SHA-256 digest, matching
+/// the fingerprint format emitted by common reverse proxies.
+fn fingerprint_sha256(cert_der: &[u8]) -> String {
+  Sha256::digest(cert_der)
+    .iter()
+    .map(|b| format!("{:02X}", b))
+    .collect::<Vec<_>>()
+    .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn option_parses_from_str() {
+    assert_eq!(
+      UpstreamOption::try_from("forward_client_cert_headers").unwrap(),
+      UpstreamOption::ForwardClientCertHeaders
+    );
+    assert_eq!(
+      UpstreamOption::try_from("disable_override_host").unwrap(),
+      UpstreamOption::DisableOverrideHost
+    );
+  }
+
+  #[test]
+  fn incoming_client_cert_headers_are_always_stripped() {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-ssl-client-subject", HeaderValue::from_static("CN=spoofed"));
+    headers.insert("host", HeaderValue::from_static("example.com"));
+
+    apply_client_cert_headers(&mut headers, &HashSet::new(), None);
+
+    assert!(headers.get("x-ssl-client-subject").is_none());
+    assert_eq!(headers.get("host").unwrap(), "example.com");
+  }
+
+  #[test]
+  fn fingerprint_is_injected_when_enabled() {
+    let mut headers = HeaderMap::new();
+    let opts = HashSet::from([UpstreamOption::ForwardClientCertHeaders]);
+    // Arbitrary bytes are not a valid certificate, so only the fingerprint
+    // (which does not require parsing) is injected.
+    apply_client_cert_headers(&mut headers, &opts, Some(b"not a certificate"));
+
+    let fp = headers.get("x-ssl-client-sha256").unwrap().to_str().unwrap();
+    // 32-byte digest rendered as colon-separated hex pairs.
+    assert_eq!(fp.len(), 32 * 3 - 1);
+    assert!(headers.get("x-ssl-client-subject").is_none());
+  }
+
+  #[test]
+  fn nothing_injected_without_a_peer_certificate() {
+    let mut headers = HeaderMap::new();
+    let opts = HashSet::from([UpstreamOption::ForwardClientCertHeaders]);
+    apply_client_cert_headers(&mut headers, &opts, None);
+    assert!(headers.keys().all(|k| !k.as_str().starts_with("x-ssl-client-")));
+  }
+}